@@ -17,6 +17,21 @@ public class CliNativeBridge {
   /** Token expected for the tooling API at version 1. */
   public static final String VERSION_V1 = "v1";
 
+  /** Status returned by {@link #pollTool(long)} while the tool is still running. */
+  public static final int TOOL_RUNNING = -1;
+
+  /** Status returned by {@link #pollTool(long)} when the tool was cancelled. */
+  public static final int TOOL_CANCELLED = -2;
+
+  /** Status returned by {@link #pollTool(long)} when the tool failed. */
+  public static final int TOOL_FAILED = -3;
+
+  /** Status returned by {@link #pollTool(long)} when the handle is unknown or has already been released. */
+  public static final int TOOL_UNKNOWN = -4;
+
+  /** Exit status for tools which are not yet wired into the native bridge; no checks were run. */
+  public static final int TOOL_NOT_WIRED = -5;
+
   /** Return the tooling protocol version. */
   public static native String apiVersion();
 
@@ -32,6 +47,100 @@ public class CliNativeBridge {
   /** Return the version string for a tool. */
   public static native String toolVersion(String toolName);
 
+  /**
+   * Return subcommands, file extensions, config file names, and format/lint/fix support for a tool, as JSON.
+   *
+   * <p>Capabilities describe what the native bridge can run for the tool, not everything the upstream tool supports.
+   * Decode the result with {@code Tools.capabilitiesFromJson}.
+   *
+   * @throws IllegalArgumentException if the tool is unknown.
+   */
+  public static native String toolCapabilities(String toolName);
+
+  /** Return the names of tools which accept the given file, based on its extension. */
+  public static native String[] toolsForFile(String file);
+
+  /** Run a tool against a single file, returning its exit code, or {@link #TOOL_NOT_WIRED}. */
+  public static native int runToolOnFile(String toolName, String file);
+
+  /**
+   * Run a tool against a single file with its own working directory and environment overrides.
+   *
+   * <p>Environment entries take the form {@code KEY=VALUE}, or {@code KEY} to unset a variable. Either argument may
+   * be {@code null}. Global state is restored once the tool finishes.
+   *
+   * <p>The overrides are applied to the whole process while the tool runs. Tool runs are serialized against each
+   * other, but other JVM threads observe the changed working directory and environment in the meantime, so avoid
+   * relying on either from other threads during the call. If the tool panics, {@link #TOOL_FAILED} is returned.
+   *
+   * @throws IllegalArgumentException if {@code cwd} is not a directory, an environment entry is {@code null}, or an
+   *   entry has an empty name or contains {@code NUL}; the tool is not run.
+   */
+  public static native int runToolOnFileInContext(String toolName, String file, String cwd, String[] env);
+
+  /**
+   * Run a tool against a single file, delivering each line of console output it reports through the bridge to a
+   * listener.
+   *
+   * <p>Only output the native tool routes through the bridge is captured; anything it prints directly to the process
+   * stdout or stderr, including output from child processes, is not delivered to the listener.
+   *
+   * <p>Lines arrive on the calling thread in the order the tool wrote them, and the listener may print them freely.
+   * If the listener throws, the exception is cleared, remaining lines are dropped, and {@link #TOOL_FAILED} is
+   * returned. {@link #TOOL_FAILED} is also returned if the tool panics.
+   *
+   * @throws NullPointerException if {@code listener} is {@code null}.
+   */
+  public static native int runToolOnFileWithOutput(String toolName, String file, ToolOutputListener listener);
+
+  /**
+   * Run a tool against a single file, returning its exit code, timings, and diagnostics as JSON.
+   *
+   * <p>Decode the result with {@code Diagnostics.resultFromJson}. Tools which are not yet wired into the native
+   * bridge report {@link #TOOL_NOT_WIRED} as their exit code, along with a {@code not-wired} note.
+   */
+  public static native String runToolOnFileForResult(String toolName, String file);
+
+  /**
+   * Start a tool in the background against a set of files, returning a handle for polling or cancellation.
+   *
+   * @throws IllegalArgumentException if {@code files} or any of its entries is {@code null}; no tool is started and
+   *   {@code 0} is returned.
+   */
+  public static native long startTool(String toolName, String[] files);
+
+  /**
+   * Return the exit code of a finished tool, or one of the {@code TOOL_*} statuses.
+   *
+   * <p>Once the exit code has been returned, the handle stays valid until its remaining progress events are drained
+   * via {@link #toolProgress(long)}. Handles which are never fully consumed are released ten minutes after the tool
+   * finishes, by the next {@code startTool}, {@code pollTool}, {@code toolProgress}, or {@code cancelTool} call.
+   */
+  public static native int pollTool(long handle);
+
+  /**
+   * Drain pending progress events for a tool, as a JSON array of {@code {percent, message}} objects.
+   *
+   * <p>Events reported before the tool finished remain available after {@link #pollTool(long)} observes completion;
+   * the handle is released once both the exit code and the remaining events have been taken.
+   */
+  public static native String toolProgress(long handle);
+
+  /** Request cooperative cancellation of a running tool; returns {@code false} if the handle is unknown. */
+  public static native boolean cancelTool(long handle);
+
+  /** Return CPU model, core counts, and per-core frequency, as JSON. */
+  public static native String cpuInfo();
+
+  /** Return 1, 5, and 15-minute load averages, as JSON. */
+  public static native String loadAverage();
+
+  /** Return total, free, and swap memory in bytes, as JSON. */
+  public static native String memoryInfo();
+
+  /** Return network interfaces and their addresses, as JSON. */
+  public static native String networkInterfaces();
+
   static {
     System.loadLibrary("umbrella");
   }
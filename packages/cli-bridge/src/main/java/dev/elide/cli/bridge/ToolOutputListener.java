@@ -0,0 +1,26 @@
+/*
+ * Copyright (c) 2024 Elide Technologies, Inc.
+ *
+ * Licensed under the MIT license (the "License"); you may not use this file except in compliance
+ * with the License. You may obtain a copy of the License at
+ *
+ *   https://opensource.org/license/mit/
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+ * an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. See the
+ * License for the specific language governing permissions and limitations under the License.
+ */
+package dev.elide.cli.bridge;
+
+/** Receives output captured from a native tool invocation, one line at a time. */
+@FunctionalInterface
+public interface ToolOutputListener {
+  /** Stream token for lines written to standard output. */
+  int STDOUT = 1;
+
+  /** Stream token for lines written to standard error. */
+  int STDERR = 2;
+
+  /** Called for each line written by the tool, without its trailing newline. */
+  void onLine(int stream, String line);
+}
@@ -43,6 +43,29 @@ import kotlinx.serialization.Serializable
   public val severity: Severity
 )
 
+@Serializable @JvmRecord public data class ProgressEvent(
+  public val percent: UByte,
+  public val message: String
+)
+
+@Serializable @JvmRecord public data class LoadAverage(
+  public val one: Double,
+  public val five: Double,
+  public val fifteen: Double
+)
+
+@Serializable @JvmRecord public data class InterfaceAddress(
+  public val address: String,
+  public val family: String,
+  public val prefix: UByte
+)
+
+@Serializable @JvmRecord public data class NetworkInterface(
+  public val name: String,
+  public val mac: String,
+  public val addresses: List<InterfaceAddress>
+)
+
 @Serializable public enum class ToolType(public val string: String) {
   @SerialName("Compiler")
   COMPILER("Compiler"),
@@ -52,10 +75,20 @@ import kotlinx.serialization.Serializable
 ;
 }
 
+@Serializable @JvmRecord public data class ToolCapabilities(
+  public val subcommands: List<String>,
+  public val extensions: List<String>,
+  public val configFiles: List<String>,
+  public val canFormat: Boolean,
+  public val canLint: Boolean,
+  public val canFix: Boolean
+)
+
 @Serializable @JvmRecord public data class ToolInfo(
   public val name: String,
   public val version: String,
   public val language: String,
   public val experimental: Boolean,
-  public val kind: ToolType
+  public val kind: ToolType,
+  public val capabilities: ToolCapabilities
 )
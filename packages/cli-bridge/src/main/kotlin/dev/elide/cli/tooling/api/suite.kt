@@ -28,6 +28,14 @@ import kotlinx.serialization.json.Json
   public val timings: DiagnosticTimings? = null,
 )
 
+@Serializable @JvmRecord public data class DiagnosticResult(
+  public val tool: String,
+  public val file: String,
+  public val success: Boolean,
+  public val exitCode: Int,
+  public val diagnostics: List<DiagnosticSuite> = emptyList(),
+)
+
 public object Diagnostics {
   @OptIn(ExperimentalSerializationApi::class)
   private val diagnosticJson: Json by lazy {
@@ -45,4 +53,9 @@ public object Diagnostics {
     DiagnosticSuite.serializer(),
     json,
   )
+
+  @JvmStatic public fun resultFromJson(json: String): DiagnosticResult = diagnosticJson.decodeFromString(
+    DiagnosticResult.serializer(),
+    json,
+  )
 }
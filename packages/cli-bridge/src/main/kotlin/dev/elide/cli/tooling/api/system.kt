@@ -0,0 +1,66 @@
+/*
+ * Copyright (c) 2024 Elide Technologies, Inc.
+ *
+ * Licensed under the MIT license (the "License"); you may not use this file except in compliance
+ * with the License. You may obtain a copy of the License at
+ *
+ *   https://opensource.org/license/mit/
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+ * an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. See the
+ * License for the specific language governing permissions and limitations under the License.
+ */
+
+package dev.elide.cli.tooling.api
+
+import kotlinx.serialization.Serializable
+import kotlinx.serialization.builtins.ListSerializer
+import kotlinx.serialization.json.Json
+
+@Serializable @JvmRecord public data class CpuInfo(
+  public val model: String,
+  public val vendor: String,
+  public val frequency: Long = 0,
+)
+
+@Serializable @JvmRecord public data class CpuSummary(
+  public val logicalCores: Int,
+  public val physicalCores: Int? = null,
+  public val cpus: List<CpuInfo>,
+)
+
+@Serializable @JvmRecord public data class MemoryInfo(
+  public val total: Long = 0,
+  public val free: Long = 0,
+  public val available: Long = 0,
+  public val totalSwap: Long = 0,
+  public val freeSwap: Long = 0,
+)
+
+public object SystemInfo {
+  private val systemJson: Json by lazy {
+    Json {
+      ignoreUnknownKeys = true
+    }
+  }
+
+  @JvmStatic public fun cpusFromJson(json: String): CpuSummary = systemJson.decodeFromString(
+    CpuSummary.serializer(),
+    json,
+  )
+
+  @JvmStatic public fun loadAverageFromJson(json: String): LoadAverage = systemJson.decodeFromString(
+    LoadAverage.serializer(),
+    json,
+  )
+
+  @JvmStatic public fun memoryFromJson(json: String): MemoryInfo = systemJson.decodeFromString(
+    MemoryInfo.serializer(),
+    json,
+  )
+
+  @JvmStatic public fun networkInterfacesFromJson(json: String): List<NetworkInterface> = systemJson.decodeFromString(
+    ListSerializer(NetworkInterface.serializer()),
+    json,
+  )
+}
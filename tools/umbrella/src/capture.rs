@@ -0,0 +1,164 @@
+/*
+ * Copyright (c) 2024 Elide Technologies, Inc.
+ *
+ * Licensed under the MIT license (the "License"); you may not use this file except in compliance
+ * with the License. You may obtain a copy of the License at
+ *
+ *   https://opensource.org/license/mit/
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+ * an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. See the
+ * License for the specific language governing permissions and limitations under the License.
+ */
+use std::sync::mpsc::{channel, Sender};
+use std::thread;
+
+#[derive(Clone, Copy, Hash, Eq, PartialEq, Debug)]
+pub enum OutputStream {
+    Stdout = 1,
+    Stderr = 2,
+}
+
+#[derive(Clone, Hash, Eq, PartialEq, Debug)]
+pub struct OutputLine {
+    pub stream: OutputStream,
+    pub line: String,
+}
+
+// Console output for a single tool invocation: the process stdio by default, or a channel when captured. Only text a
+// tool passes through here is captured; anything it prints directly (`println!`, `tracing`, child processes) still goes
+// to the process descriptors.
+#[derive(Clone, Debug, Default)]
+pub struct ToolOutput {
+    sink: Option<Sender<OutputLine>>,
+}
+
+impl ToolOutput {
+    pub fn stdout(&self, text: impl AsRef<str>) {
+        self.write(OutputStream::Stdout, text.as_ref());
+    }
+
+    pub fn stderr(&self, text: impl AsRef<str>) {
+        self.write(OutputStream::Stderr, text.as_ref());
+    }
+
+    // Both streams share one channel, so lines keep the order in which the tool wrote them.
+    pub fn write(&self, stream: OutputStream, text: &str) {
+        let tx = match &self.sink {
+            Some(tx) => tx,
+            None => {
+                match stream {
+                    OutputStream::Stdout => println!("{}", text),
+                    OutputStream::Stderr => eprintln!("{}", text),
+                }
+                return;
+            }
+        };
+        if text.is_empty() {
+            let _ = tx.send(OutputLine {
+                stream,
+                line: String::new(),
+            });
+        }
+        for line in text.lines() {
+            let _ = tx.send(OutputLine {
+                stream,
+                line: line.to_string(),
+            });
+        }
+    }
+}
+
+// Run `op` on a worker thread with a capturing `ToolOutput`, delivering each line to `onLine` on the calling thread as
+// it is written. Once `onLine` returns `false`, delivery stops and remaining lines are discarded. A panic in `op` is
+// returned as an error rather than re-raised on the calling thread.
+pub fn captureOutput<R, F, L>(op: F, mut onLine: L) -> thread::Result<R>
+where
+    R: Send,
+    F: FnOnce(&ToolOutput) -> R + Send,
+    L: FnMut(OutputLine) -> bool,
+{
+    let (tx, rx) = channel::<OutputLine>();
+    thread::scope(|scope| {
+        let worker = scope.spawn(move || op(&ToolOutput { sink: Some(tx) }));
+
+        // the channel closes once the worker returns and drops its output
+        let mut delivering = true;
+        for line in rx {
+            if delivering {
+                delivering = onLine(line);
+            }
+        }
+        worker.join()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keepsStreamOrder() {
+        let mut lines: Vec<OutputLine> = vec![];
+        let exit = captureOutput(
+            |out| {
+                out.stdout("one\ntwo");
+                out.stderr("three");
+                out.stdout("");
+                4
+            },
+            |line| {
+                lines.push(line);
+                true
+            },
+        );
+        assert_eq!(exit.unwrap(), 4);
+        let seen: Vec<(OutputStream, &str)> =
+            lines.iter().map(|x| (x.stream, x.line.as_str())).collect();
+        assert_eq!(
+            seen,
+            vec![
+                (OutputStream::Stdout, "one"),
+                (OutputStream::Stdout, "two"),
+                (OutputStream::Stderr, "three"),
+                (OutputStream::Stdout, ""),
+            ]
+        );
+    }
+
+    #[test]
+    fn stopsDeliveringWhenRejected() {
+        let mut delivered = 0;
+        let exit = captureOutput(
+            |out| {
+                for i in 0..10 {
+                    out.stdout(i.to_string());
+                }
+                0
+            },
+            |_| {
+                delivered += 1;
+                false
+            },
+        );
+        assert_eq!(exit.unwrap(), 0);
+        assert_eq!(delivered, 1);
+    }
+
+    #[test]
+    fn returnsToolPanic() {
+        let mut lines = 0;
+        let exit = captureOutput(
+            |out| {
+                out.stdout("before");
+                panic!("tool failure");
+            },
+            |_| {
+                lines += 1;
+                true
+            },
+        );
+        assert!(exit.is_err());
+        assert_eq!(lines, 1);
+    }
+}
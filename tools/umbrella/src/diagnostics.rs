@@ -11,9 +11,10 @@
  * License for the specific language governing permissions and limitations under the License.
  */
 use serde::Serialize;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 #[typeshare::typeshare]
-#[derive(Clone, Hash, Eq, PartialEq, Debug, Serialize)]
+#[derive(Clone, Hash, Eq, PartialEq, PartialOrd, Ord, Debug, Serialize)]
 pub enum Severity {
     Info,
     Warning,
@@ -23,7 +24,7 @@ pub enum Severity {
 #[typeshare::typeshare]
 #[derive(Clone, Hash, Eq, PartialEq, Debug, Serialize)]
 pub struct CodeLocation {
-    pub file: &'static str,
+    pub file: String,
     pub line: u32,
     pub column: u32,
 }
@@ -31,14 +32,15 @@ pub struct CodeLocation {
 #[typeshare::typeshare]
 #[derive(Clone, Hash, Eq, PartialEq, Debug, Serialize)]
 pub struct DiagnosticNote {
-    pub id: &'static str,
+    pub id: String,
     pub tool: &'static str,
-    pub code: &'static str,
-    pub message: &'static str,
+    pub code: String,
+    pub message: String,
     pub location: CodeLocation,
     pub severity: Severity,
 }
 
+// Timings are 64-bit, which typeshare can't express; this type and those containing it are modeled by hand in `suite.kt`.
 #[derive(Clone, Hash, Eq, PartialEq, Debug, Serialize)]
 pub struct DiagnosticTimings {
     pub start: u64,
@@ -54,7 +56,133 @@ pub struct DiagnosticSuite {
 
 #[derive(Clone, Hash, Eq, PartialEq, Debug, Serialize)]
 pub struct DiagnosticResult {
+    pub tool: &'static str,
+    pub file: String,
     pub success: bool,
     pub exitCode: i32,
     pub diagnostics: Vec<DiagnosticSuite>,
 }
+
+// What a single tool run produced: its exit code, plus any notes the tool reported.
+#[derive(Clone, Hash, Eq, PartialEq, Debug)]
+pub struct ToolOutcome {
+    pub exitCode: i32,
+    pub notes: Vec<DiagnosticNote>,
+}
+
+impl ToolOutcome {
+    pub fn exit(exitCode: i32) -> ToolOutcome {
+        ToolOutcome {
+            exitCode,
+            notes: vec![],
+        }
+    }
+}
+
+// Milliseconds since the Unix epoch, used for diagnostic timings.
+pub fn nowMillis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+impl DiagnosticSuite {
+    pub fn new(notes: Vec<DiagnosticNote>, timings: DiagnosticTimings) -> DiagnosticSuite {
+        let maxSeverity = notes
+            .iter()
+            .map(|note| note.severity.clone())
+            .max()
+            .unwrap_or(Severity::Info);
+        DiagnosticSuite {
+            maxSeverity,
+            notes,
+            timings,
+        }
+    }
+}
+
+impl DiagnosticResult {
+    pub fn new(
+        tool: &'static str,
+        file: &str,
+        exitCode: i32,
+        diagnostics: Vec<DiagnosticSuite>,
+    ) -> DiagnosticResult {
+        DiagnosticResult {
+            tool,
+            file: file.to_string(),
+            success: exitCode == 0,
+            exitCode,
+            diagnostics,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn note(severity: Severity) -> DiagnosticNote {
+        DiagnosticNote {
+            id: "ruff:E501".to_string(),
+            tool: "ruff",
+            code: "E501".to_string(),
+            message: "Line too long".to_string(),
+            location: CodeLocation {
+                file: "main.py".to_string(),
+                line: 3,
+                column: 89,
+            },
+            severity,
+        }
+    }
+
+    #[test]
+    fn suiteTakesMaxSeverity() {
+        let timings = DiagnosticTimings { start: 1, end: 2 };
+        let notes = vec![
+            note(Severity::Warning),
+            note(Severity::Error),
+            note(Severity::Info),
+        ];
+        let suite = DiagnosticSuite::new(notes, timings.clone());
+        assert_eq!(suite.maxSeverity, Severity::Error);
+        assert_eq!(suite.notes.len(), 3);
+        assert_eq!(
+            DiagnosticSuite::new(vec![], timings).maxSeverity,
+            Severity::Info
+        );
+    }
+
+    #[test]
+    fn resultJsonShape() {
+        let suite = DiagnosticSuite::new(
+            vec![note(Severity::Warning)],
+            DiagnosticTimings { start: 10, end: 25 },
+        );
+        let result = DiagnosticResult::new("ruff", "main.py", 1, vec![suite]);
+        assert_eq!(
+            serde_json::to_value(&result).unwrap(),
+            json!({
+                "tool": "ruff",
+                "file": "main.py",
+                "success": false,
+                "exitCode": 1,
+                "diagnostics": [{
+                    "maxSeverity": "Warning",
+                    "notes": [{
+                        "id": "ruff:E501",
+                        "tool": "ruff",
+                        "code": "E501",
+                        "message": "Line too long",
+                        "location": {"file": "main.py", "line": 3, "column": 89},
+                        "severity": "Warning",
+                    }],
+                    "timings": {"start": 10, "end": 25},
+                }],
+            })
+        );
+    }
+}
@@ -0,0 +1,294 @@
+/*
+ * Copyright (c) 2024 Elide Technologies, Inc.
+ *
+ * Licensed under the MIT license (the "License"); you may not use this file except in compliance
+ * with the License. You may obtain a copy of the License at
+ *
+ *   https://opensource.org/license/mit/
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+ * an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. See the
+ * License for the specific language governing permissions and limitations under the License.
+ */
+use crate::progress::{ProgressEvent, ProgressReporter};
+use lazy_static::lazy_static;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+// Poll status: the invocation is still running.
+pub const STATUS_RUNNING: i32 = -1;
+
+// Poll status: the invocation stopped early because it was cancelled; tools return this when they observe cancellation.
+pub const STATUS_CANCELLED: i32 = -2;
+
+// Poll status: the invocation panicked.
+pub const STATUS_FAILED: i32 = -3;
+
+// Poll status: the handle was never issued, or has already been released.
+pub const STATUS_UNKNOWN: i32 = -4;
+
+// Exit status: the tool's native integration is still a stub, so nothing was checked.
+pub const STATUS_NOT_WIRED: i32 = -5;
+
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn isCancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+struct Invocation {
+    cancel: CancelToken,
+    progress: ProgressReporter,
+    worker: Option<JoinHandle<i32>>,
+    // set by the `poll` which observes completion, so the code has been handed out once set
+    exit: Option<i32>,
+    finishedAt: Arc<OnceLock<Instant>>,
+}
+
+// Stamps the time its invocation finished when dropped, which happens as the worker exits, even if the tool panics.
+struct FinishStamp(Arc<OnceLock<Instant>>);
+
+impl Drop for FinishStamp {
+    fn drop(&mut self) {
+        let _ = self.0.set(Instant::now());
+    }
+}
+
+impl Invocation {
+    fn isFinished(&self) -> bool {
+        self.worker
+            .as_ref()
+            .is_none_or(|worker| worker.is_finished())
+    }
+}
+
+// Finished invocations which are never fully consumed are released this long after they finish.
+const FINISHED_TTL: Duration = Duration::from_secs(600);
+
+static NEXT_HANDLE: AtomicI64 = AtomicI64::new(1);
+
+lazy_static! {
+    static ref INVOCATIONS: Mutex<HashMap<i64, Invocation>> = Mutex::new(HashMap::new());
+}
+
+// Start `op` on a background thread, returning a handle for `poll` and `cancel`.
+pub fn start<F>(op: F) -> i64
+where
+    F: FnOnce(&CancelToken, &ProgressReporter) -> i32 + Send + 'static,
+{
+    let handle = NEXT_HANDLE.fetch_add(1, Ordering::SeqCst);
+    let cancel = CancelToken::default();
+    let progress = ProgressReporter::default();
+    let token = cancel.clone();
+    let reporter = progress.clone();
+    let finishedAt = Arc::new(OnceLock::new());
+    let stamp = FinishStamp(finishedAt.clone());
+    let worker = thread::Builder::new()
+        .name(format!("elide-tool-{}", handle))
+        .spawn(move || {
+            let _stamp = stamp;
+            op(&token, &reporter)
+        })
+        .expect("Failed to spawn tool thread");
+
+    let mut invocations = INVOCATIONS.lock().unwrap();
+    sweep(&mut invocations, Instant::now());
+    invocations.insert(
+        handle,
+        Invocation {
+            cancel,
+            progress,
+            worker: Some(worker),
+            exit: None,
+            finishedAt,
+        },
+    );
+    handle
+}
+
+// Check on an invocation; once it has finished, its exit code is returned. The handle is released once both the exit
+// code and any remaining progress events have been taken.
+pub fn poll(handle: i64) -> i32 {
+    let mut invocations = INVOCATIONS.lock().unwrap();
+    sweep(&mut invocations, Instant::now());
+    let inv = match invocations.get_mut(&handle) {
+        Some(inv) => inv,
+        None => return STATUS_UNKNOWN,
+    };
+    if let Some(worker) = inv.worker.take_if(|worker| worker.is_finished()) {
+        inv.exit = Some(worker.join().unwrap_or(STATUS_FAILED));
+    }
+    let code = match inv.exit {
+        Some(code) => code,
+        None => return STATUS_RUNNING,
+    };
+    if inv.progress.isEmpty() {
+        invocations.remove(&handle);
+    }
+    code
+}
+
+// Request cancellation; tools observe it at their next checkpoint. Returns `false` for unknown handles.
+pub fn cancel(handle: i64) -> bool {
+    let mut invocations = INVOCATIONS.lock().unwrap();
+    sweep(&mut invocations, Instant::now());
+    match invocations.get(&handle) {
+        Some(inv) => {
+            inv.cancel.cancel();
+            true
+        }
+        None => false,
+    }
+}
+
+// Take pending progress events. Events outlive completion, so a drain after the final `poll` still sees them.
+pub fn progress(handle: i64) -> Vec<ProgressEvent> {
+    let mut invocations = INVOCATIONS.lock().unwrap();
+    sweep(&mut invocations, Instant::now());
+    let inv = match invocations.get(&handle) {
+        Some(inv) => inv,
+        None => return vec![],
+    };
+    let events = inv.progress.drain();
+    if inv.exit.is_some() {
+        invocations.remove(&handle);
+    }
+    events
+}
+
+// Drop invocations which finished more than `FINISHED_TTL` before `now` without being fully consumed. Runs on every
+// call which takes the invocation lock.
+fn sweep(invocations: &mut HashMap<i64, Invocation>, now: Instant) {
+    invocations.retain(|_, inv| {
+        inv.finishedAt
+            .get()
+            .is_none_or(|at| now.saturating_duration_since(*at) < FINISHED_TTL)
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc::channel;
+
+    fn waitFor(handle: i64) -> i32 {
+        loop {
+            let status = poll(handle);
+            if status != STATUS_RUNNING {
+                return status;
+            }
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn pollReportsRunningThenExitCodeThenReleases() {
+        let (gate, wait) = channel::<()>();
+        let handle = start(move |_, _| {
+            wait.recv().unwrap();
+            7
+        });
+        assert_eq!(poll(handle), STATUS_RUNNING);
+        gate.send(()).unwrap();
+        assert_eq!(waitFor(handle), 7);
+        assert_eq!(poll(handle), STATUS_UNKNOWN);
+    }
+
+    #[test]
+    fn cancelBeforeStartReportsCancelled() {
+        let (gate, wait) = channel::<()>();
+        let handle = start(move |token, _| {
+            wait.recv().unwrap();
+            if token.isCancelled() {
+                STATUS_CANCELLED
+            } else {
+                0
+            }
+        });
+        assert!(cancel(handle));
+        gate.send(()).unwrap();
+        assert_eq!(waitFor(handle), STATUS_CANCELLED);
+        assert!(!cancel(handle));
+    }
+
+    #[test]
+    fn cancelAfterCompletionKeepsExitCode() {
+        let handle = start(|_, _| 0);
+        while !INVOCATIONS.lock().unwrap()[&handle].isFinished() {
+            thread::sleep(Duration::from_millis(1));
+        }
+        assert!(cancel(handle));
+        assert_eq!(poll(handle), 0);
+    }
+
+    #[test]
+    fn finalProgressSurvivesCompletion() {
+        let handle = start(|_, progress| {
+            progress.report(50, "halfway");
+            progress.report(100, "done");
+            0
+        });
+        while !INVOCATIONS.lock().unwrap()[&handle].isFinished() {
+            thread::sleep(Duration::from_millis(1));
+        }
+        assert_eq!(poll(handle), 0);
+        let events = progress(handle);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[1].percent, 100);
+        assert_eq!(events[1].message, "done");
+        assert_eq!(poll(handle), STATUS_UNKNOWN);
+    }
+
+    #[test]
+    fn drainingProgressFirstLeavesExitCode() {
+        let handle = start(|_, progress| {
+            progress.report(100, "done");
+            3
+        });
+        while !INVOCATIONS.lock().unwrap()[&handle].isFinished() {
+            thread::sleep(Duration::from_millis(1));
+        }
+        assert_eq!(progress(handle).len(), 1);
+        assert_eq!(poll(handle), 3);
+        assert_eq!(poll(handle), STATUS_UNKNOWN);
+    }
+
+    #[test]
+    fn panickingToolReportsFailed() {
+        let handle = start(|_, _| panic!("tool failure"));
+        assert_eq!(waitFor(handle), STATUS_FAILED);
+    }
+
+    #[test]
+    fn sweepReleasesStaleInvocations() {
+        let handle = start(|_, _| panic!("tool failure"));
+        let finishedAt = INVOCATIONS.lock().unwrap()[&handle].finishedAt.clone();
+        while finishedAt.get().is_none() {
+            thread::sleep(Duration::from_millis(1));
+        }
+        let mut invocations = HashMap::new();
+        invocations.insert(handle, INVOCATIONS.lock().unwrap().remove(&handle).unwrap());
+
+        sweep(&mut invocations, Instant::now());
+        assert_eq!(invocations.len(), 1);
+        sweep(&mut invocations, *finishedAt.get().unwrap() + FINISHED_TTL);
+        assert!(invocations.is_empty());
+    }
+
+    #[test]
+    fn unknownHandleReportsUnknown() {
+        assert_eq!(poll(0), STATUS_UNKNOWN);
+        assert!(progress(0).is_empty());
+        assert!(!cancel(0));
+    }
+}
@@ -0,0 +1,222 @@
+/*
+ * Copyright (c) 2024 Elide Technologies, Inc.
+ *
+ * Licensed under the MIT license (the "License"); you may not use this file except in compliance
+ * with the License. You may obtain a copy of the License at
+ *
+ *   https://opensource.org/license/mit/
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+ * an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. See the
+ * License for the specific language governing permissions and limitations under the License.
+ */
+use std::env;
+use std::ffi::OsString;
+use std::fmt;
+use std::panic;
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+use std::thread;
+
+// Per-invocation overrides for process-wide state; `None` values unset the variable.
+#[derive(Clone, Debug, Default)]
+pub struct ToolContext {
+    pub cwd: Option<PathBuf>,
+    pub env: Vec<(String, Option<String>)>,
+}
+
+#[derive(Clone, Hash, Eq, PartialEq, Debug)]
+pub enum ContextError {
+    Cwd(PathBuf),
+    EnvKey(String),
+    EnvValue(String),
+}
+
+impl fmt::Display for ContextError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ContextError::Cwd(cwd) => {
+                write!(f, "Working directory is not a directory: {}", cwd.display())
+            }
+            ContextError::EnvKey(key) => {
+                write!(f, "Invalid environment variable name: {:?}", key)
+            }
+            ContextError::EnvValue(key) => {
+                write!(f, "Invalid value for environment variable: {}", key)
+            }
+        }
+    }
+}
+
+impl ToolContext {
+    // Parse `KEY=VALUE` entries as overrides and bare `KEY` entries as removals.
+    pub fn withEnvEntries(mut self, entries: &[String]) -> ToolContext {
+        for entry in entries {
+            match entry.split_once('=') {
+                Some((key, value)) => self.env.push((key.to_string(), Some(value.to_string()))),
+                None => self.env.push((entry.to_string(), None)),
+            }
+        }
+        self
+    }
+
+    // Check everything `std::env` would otherwise panic on, since overrides arrive unchecked from the JVM.
+    pub fn validate(&self) -> Result<(), ContextError> {
+        if let Some(cwd) = &self.cwd {
+            if !cwd.is_dir() {
+                return Err(ContextError::Cwd(cwd.clone()));
+            }
+        }
+        for (key, value) in &self.env {
+            if key.is_empty() || key.contains(['=', '\0']) {
+                return Err(ContextError::EnvKey(key.clone()));
+            }
+            if value.as_ref().is_some_and(|value| value.contains('\0')) {
+                return Err(ContextError::EnvValue(key.clone()));
+            }
+        }
+        Ok(())
+    }
+}
+
+// Tools mutate cwd, env, and the panic hook as if they owned the process, so invocations hold this while running. It
+// only serializes tools against each other: the overrides are still process-wide while a tool runs, so other JVM
+// threads see them, and their `getenv` or relative-path calls race with the changes.
+static GLOBAL_STATE_LOCK: Mutex<()> = Mutex::new(());
+
+struct SavedState {
+    cwd: Option<PathBuf>,
+    env: Vec<(String, Option<OsString>)>,
+    _guard: MutexGuard<'static, ()>,
+}
+
+impl SavedState {
+    fn apply(ctx: &ToolContext) -> Result<SavedState, ContextError> {
+        ctx.validate()?;
+        let guard = GLOBAL_STATE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let saved = SavedState {
+            cwd: env::current_dir().ok(),
+            env: ctx
+                .env
+                .iter()
+                .map(|(key, _)| (key.clone(), env::var_os(key)))
+                .collect(),
+            _guard: guard,
+        };
+        if let Some(cwd) = &ctx.cwd {
+            // the directory may have vanished since validation
+            env::set_current_dir(cwd).map_err(|_| ContextError::Cwd(cwd.clone()))?;
+        }
+        for (key, value) in &ctx.env {
+            match value {
+                Some(value) => env::set_var(key, value),
+                None => env::remove_var(key),
+            }
+        }
+        Ok(saved)
+    }
+}
+
+impl Drop for SavedState {
+    fn drop(&mut self) {
+        // restore in reverse so repeated keys end up with their original value
+        for (key, value) in self.env.iter().rev() {
+            match value {
+                Some(value) => env::set_var(key, value),
+                None => env::remove_var(key),
+            }
+        }
+        if let Some(cwd) = &self.cwd {
+            let _ = env::set_current_dir(cwd);
+        }
+    }
+}
+
+// Run `op` with `ctx` applied, serialized against other tools; all global state is restored afterward, even on panic.
+// A panic in `op` is caught and returned rather than re-raised, so it can't unwind into a JNI caller. Fails without
+// running `op` if the context is invalid.
+pub fn isolated<R>(
+    ctx: &ToolContext,
+    op: impl FnOnce() -> R,
+) -> Result<thread::Result<R>, ContextError> {
+    let saved = SavedState::apply(ctx)?;
+    let hook = panic::take_hook();
+    let result = panic::catch_unwind(panic::AssertUnwindSafe(op));
+    panic::set_hook(hook);
+    drop(saved);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Tests below read process-wide state outside `isolated`, so they must not interleave.
+    static TEST_LOCK: Mutex<()> = Mutex::new(());
+
+    fn envContext(entries: &[&str]) -> ToolContext {
+        let entries: Vec<String> = entries.iter().map(|x| x.to_string()).collect();
+        ToolContext::default().withEnvEntries(&entries)
+    }
+
+    #[test]
+    fn parsesEnvEntries() {
+        let ctx = envContext(&["A=1", "B", "C=x=y", "D="]);
+        assert_eq!(
+            ctx.env,
+            vec![
+                ("A".to_string(), Some("1".to_string())),
+                ("B".to_string(), None),
+                ("C".to_string(), Some("x=y".to_string())),
+                ("D".to_string(), Some(String::new())),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejectsInvalidContexts() {
+        let _lock = TEST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        for entry in ["=FOO", "", "A\0B=1"] {
+            let ctx = envContext(&[entry]);
+            assert!(matches!(ctx.validate(), Err(ContextError::EnvKey(_))));
+            assert!(isolated(&ctx, || ()).is_err());
+        }
+        let ctx = envContext(&["A=1\0"]);
+        assert!(matches!(ctx.validate(), Err(ContextError::EnvValue(_))));
+
+        let ctx = ToolContext {
+            cwd: Some(PathBuf::from("/nonexistent/umbrella-tool-cwd")),
+            env: vec![],
+        };
+        assert!(matches!(isolated(&ctx, || ()), Err(ContextError::Cwd(_))));
+    }
+
+    #[test]
+    fn restoresStateAfterPanic() {
+        let _lock = TEST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let cwd = env::current_dir().unwrap();
+        env::set_var("UMBRELLA_TEST_KEPT", "original");
+        env::remove_var("UMBRELLA_TEST_ADDED");
+
+        let mut ctx = envContext(&[
+            "UMBRELLA_TEST_KEPT=first",
+            "UMBRELLA_TEST_KEPT=second",
+            "UMBRELLA_TEST_ADDED=value",
+        ]);
+        ctx.cwd = Some(env::temp_dir());
+        let result = isolated(&ctx, || {
+            assert_eq!(env::var("UMBRELLA_TEST_KEPT").unwrap(), "second");
+            assert_eq!(env::var("UMBRELLA_TEST_ADDED").unwrap(), "value");
+            assert_eq!(
+                env::current_dir().unwrap().canonicalize().unwrap(),
+                env::temp_dir().canonicalize().unwrap()
+            );
+            panic!("tool failure");
+        });
+
+        assert!(matches!(result, Ok(Err(_))));
+        assert_eq!(env::var("UMBRELLA_TEST_KEPT").unwrap(), "original");
+        assert!(env::var_os("UMBRELLA_TEST_ADDED").is_none());
+        assert_eq!(env::current_dir().unwrap(), cwd);
+    }
+}
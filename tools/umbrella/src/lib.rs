@@ -12,13 +12,27 @@
  */
 #![allow(non_snake_case, dead_code)]
 
+mod capture;
 mod diagnostics;
+mod invocation;
+mod isolation;
+mod progress;
+mod system;
 mod tools;
 mod transport;
 
+use crate::capture::{captureOutput, OutputLine, ToolOutput};
+use crate::diagnostics::{
+    nowMillis, CodeLocation, DiagnosticNote, DiagnosticResult, DiagnosticSuite, DiagnosticTimings,
+    Severity, ToolOutcome,
+};
+use crate::invocation::CancelToken;
+use crate::isolation::{isolated, ContextError, ToolContext};
+use crate::progress::ProgressReporter;
 use crate::tools::{ToolInfo, API_VERSION, LIB_VERSION, OXY_INFO, RUFF_INFO, UV_INFO};
-use jni::objects::{JClass, JString};
-use jni::sys::{jint, jobjectArray, jstring};
+use jni::objects::{JClass, JMethodID, JObject, JObjectArray, JString, JValue};
+use jni::signature::{Primitive, ReturnType};
+use jni::sys::{jboolean, jint, jlong, jobjectArray, jstring, JNI_FALSE, JNI_TRUE};
 use jni::JNIEnv;
 use lazy_static::lazy_static;
 use std::collections::HashMap;
@@ -34,35 +48,47 @@ lazy_static! {
 }
 
 // -- Entrypoint Functions
+
+// Outcome for tools whose native integration is still a stub; the distinct exit status keeps callers from mistaking
+// "not checked" for "clean".
+fn notWired(tool: &'static str, file: &str) -> ToolOutcome {
+    ToolOutcome {
+        exitCode: invocation::STATUS_NOT_WIRED,
+        notes: vec![DiagnosticNote {
+            id: format!("{}:not-wired", tool),
+            tool,
+            code: "not-wired".to_string(),
+            message: format!(
+                "{} is not wired into the native bridge yet; no checks were run",
+                tool
+            ),
+            location: CodeLocation {
+                file: file.to_string(),
+                line: 0,
+                column: 0,
+            },
+            severity: Severity::Info,
+        }],
+    }
+}
+
 fn supportedTools() -> Vec<&'static str> {
     TOOL_MAP.keys().map(|&x| x).collect()
 }
 
-fn runUvOnSingleFile(mut env: JNIEnv, file: &JString) -> jint {
-    let input: String = env
-        .get_string(&file)
-        .expect("Couldn't get file string")
-        .into();
-    println!("Running uv on file: {}", input);
-    0
+fn runUvOnSingleFile(input: &str, out: &ToolOutput) -> ToolOutcome {
+    out.stdout(format!("Running uv on file: {}", input));
+    notWired("uv", input)
 }
 
-fn runOxyOnSingleFile(mut env: JNIEnv, file: &JString) -> jint {
-    let input: String = env
-        .get_string(&file)
-        .expect("Couldn't get file string")
-        .into();
-    println!("Running oxy on file: {}", input);
-    0
+fn runOxyOnSingleFile(input: &str, out: &ToolOutput) -> ToolOutcome {
+    out.stdout(format!("Running oxy on file: {}", input));
+    notWired("oxy", input)
 }
 
-fn runRuffOnSingleFile(mut env: JNIEnv, file: &JString) -> jint {
-    let input: String = env
-        .get_string(&file)
-        .expect("Couldn't get file string")
-        .into();
-    println!("Running ruff on file: {}", input);
-    return 0;
+fn runRuffOnSingleFile(input: &str, out: &ToolOutput) -> ToolOutcome {
+    out.stdout(format!("Running ruff on file: {}", input));
+    return notWired("ruff", input);
     // let checkCommand: CheckCommand = CheckCommand {
     //   files: vec![PathBuf::from(input)],
     //   // output_format: SerializationFormat::Json,
@@ -83,6 +109,141 @@ fn runRuffOnSingleFile(mut env: JNIEnv, file: &JString) -> jint {
     // }
 }
 
+fn runToolOnSingleFile(tool: &ToolInfo, file: &str, out: &ToolOutput) -> ToolOutcome {
+    // the default context changes nothing, so it can't fail validation
+    runToolInContext(tool, file, &ToolContext::default(), out)
+        .unwrap_or_else(|_| ToolOutcome::exit(invocation::STATUS_FAILED))
+}
+
+fn runToolInContext(
+    tool: &ToolInfo,
+    file: &str,
+    ctx: &ToolContext,
+    out: &ToolOutput,
+) -> Result<ToolOutcome, ContextError> {
+    isolated(ctx, || dispatchTool(tool, file, out))
+        .map(|result| result.unwrap_or_else(|_| ToolOutcome::exit(invocation::STATUS_FAILED)))
+}
+
+fn dispatchTool(tool: &ToolInfo, file: &str, out: &ToolOutput) -> ToolOutcome {
+    // switch by tool name
+    match tool.name {
+        "uv" => runUvOnSingleFile(file, out),
+        "oxy" => runOxyOnSingleFile(file, out),
+        "ruff" => runRuffOnSingleFile(file, out),
+        _ => ToolOutcome::exit(1),
+    }
+}
+
+// Run a tool and wrap its exit code, timings, and reported notes in a structured result.
+fn runToolForResult(tool: &ToolInfo, file: &str) -> DiagnosticResult {
+    let start = nowMillis();
+    let outcome = runToolOnSingleFile(tool, file, &ToolOutput::default());
+    let timings = DiagnosticTimings {
+        start,
+        end: nowMillis(),
+    };
+    DiagnosticResult::new(
+        tool.name,
+        file,
+        outcome.exitCode,
+        vec![DiagnosticSuite::new(outcome.notes, timings)],
+    )
+}
+
+fn runToolOnFiles(
+    tool: &ToolInfo,
+    files: &[String],
+    cancel: &CancelToken,
+    progress: &ProgressReporter,
+) -> jint {
+    for (i, file) in files.iter().enumerate() {
+        if cancel.isCancelled() {
+            return invocation::STATUS_CANCELLED;
+        }
+        progress.report(
+            (i * 100 / files.len()) as u8,
+            format!("{}: {}", tool.name, file),
+        );
+        let exit = runToolOnSingleFile(tool, file, &ToolOutput::default()).exitCode;
+        if exit != 0 {
+            return exit;
+        }
+    }
+    progress.report(100, format!("{}: done", tool.name));
+    0
+}
+
+// Throw `IllegalArgumentException` into the JVM; callers return a placeholder value once it is pending.
+fn throwIllegalArgument(env: &mut JNIEnv, message: impl AsRef<str>) {
+    let _ = env.throw_new("java/lang/IllegalArgumentException", message);
+}
+
+// Look up a tool by name; a null or unknown name throws `IllegalArgumentException` and returns `None`.
+fn toolNamed(env: &mut JNIEnv, name: &JString) -> Option<&'static ToolInfo> {
+    if name.is_null() {
+        throwIllegalArgument(env, "Tool name is null");
+        return None;
+    }
+    let input: String = env.get_string(name).ok()?.into();
+    match TOOL_MAP.get(input.as_str()) {
+        Some(tool) => Some(*tool),
+        None => {
+            throwIllegalArgument(env, format!("Tool not found: {}", input));
+            None
+        }
+    }
+}
+
+// Read a Java `String[]`; a null array or element throws `IllegalArgumentException` and returns `None`.
+fn stringArray(env: &mut JNIEnv, array: &JObjectArray) -> Option<Vec<String>> {
+    if array.is_null() {
+        throwIllegalArgument(env, "Array is null");
+        return None;
+    }
+    let count = env.get_array_length(array).ok()?;
+    let mut values: Vec<String> = Vec::with_capacity(count as usize);
+    for i in 0..count {
+        let value = JString::from(env.get_object_array_element(array, i).ok()?);
+        if value.is_null() {
+            throwIllegalArgument(env, format!("Array element {} is null", i));
+            return None;
+        }
+        values.push(env.get_string(&value).ok()?.into());
+    }
+    Some(values)
+}
+
+// Call `listener.onLine`; if it throws, the exception is cleared and `false` is returned rather than unwinding into the JVM.
+#[allow(unsafe_code)]
+fn deliverLine(env: &mut JNIEnv, listener: &JObject, onLine: JMethodID, out: OutputLine) -> bool {
+    let line = match env.new_string(out.line) {
+        Ok(line) => line,
+        Err(_) => {
+            let _ = env.exception_clear();
+            return false;
+        }
+    };
+    // safe: `onLine` was resolved against `ToolOutputListener` with this signature, and `listener` implements it
+    let result = unsafe {
+        env.call_method_unchecked(
+            listener,
+            onLine,
+            ReturnType::Primitive(Primitive::Void),
+            &[
+                JValue::Int(out.stream as i32).as_jni(),
+                JValue::Object(&line).as_jni(),
+            ],
+        )
+    };
+    let threw = result.is_err() || env.exception_check().unwrap_or(true);
+    if threw {
+        let _ = env.exception_clear();
+    }
+    let _ = env.delete_local_ref(line);
+    !threw
+}
+
 // -- JNI Aliases
 
 #[no_mangle]
@@ -129,14 +290,9 @@ pub extern "C" fn Java_dev_elide_cli_bridge_CliNativeBridge_relatesTo<'local>(
     _class: JClass,
     tool: JString<'local>,
 ) -> jobjectArray {
-    let input: String = env
-        .get_string(&tool)
-        .expect("Couldn't get tool string")
-        .into();
-    let toolInfo = TOOL_MAP.get(input.as_str());
-    let tool = match toolInfo {
+    let tool = match toolNamed(&mut env, &tool) {
         Some(tool) => tool,
-        None => panic!("Tool not found"),
+        None => return std::ptr::null_mut(),
     };
     let array = env
         .new_object_array(1, "java/lang/String", env.new_string("").unwrap())
@@ -154,14 +310,9 @@ pub extern "C" fn Java_dev_elide_cli_bridge_CliNativeBridge_toolVersion<'local>(
     _class: JClass,
     tool: JString<'local>,
 ) -> jstring {
-    let input: String = env
-        .get_string(&tool)
-        .expect("Couldn't get tool string")
-        .into();
-    let toolInfo = TOOL_MAP.get(input.as_str());
-    let tool = match toolInfo {
+    let tool = match toolNamed(&mut env, &tool) {
         Some(tool) => tool,
-        None => panic!("Tool not found"),
+        None => return std::ptr::null_mut(),
     };
     env.new_string(tool.version).unwrap().into_raw()
 }
@@ -173,21 +324,257 @@ pub extern "C" fn Java_dev_elide_cli_bridge_CliNativeBridge_runToolOnFile<'local
     tool: JString<'local>,
     file: JString<'local>,
 ) -> jint {
-    let input: String = env
-        .get_string(&tool)
-        .expect("Couldn't get tool string")
+    let tool = match toolNamed(&mut env, &tool) {
+        Some(tool) => tool,
+        None => return invocation::STATUS_FAILED,
+    };
+
+    let file: String = env
+        .get_string(&file)
+        .expect("Couldn't get file string")
+        .into();
+    runToolOnSingleFile(tool, &file, &ToolOutput::default()).exitCode
+}
+
+#[no_mangle]
+pub extern "C" fn Java_dev_elide_cli_bridge_CliNativeBridge_runToolOnFileInContext<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass,
+    tool: JString<'local>,
+    file: JString<'local>,
+    cwd: JString<'local>,
+    environ: JObjectArray<'local>,
+) -> jint {
+    let tool = match toolNamed(&mut env, &tool) {
+        Some(tool) => tool,
+        None => return invocation::STATUS_FAILED,
+    };
+    let file: String = env
+        .get_string(&file)
+        .expect("Couldn't get file string")
         .into();
-    let toolInfo = TOOL_MAP.get(input.as_str());
-    let tool = match toolInfo {
+
+    let mut ctx = ToolContext::default();
+    if !cwd.is_null() {
+        let cwd: String = env
+            .get_string(&cwd)
+            .expect("Couldn't get cwd string")
+            .into();
+        ctx.cwd = Some(cwd.into());
+    }
+    if !environ.is_null() {
+        match stringArray(&mut env, &environ) {
+            Some(entries) => ctx = ctx.withEnvEntries(&entries),
+            None => return invocation::STATUS_FAILED,
+        }
+    }
+    match runToolInContext(tool, &file, &ctx, &ToolOutput::default()) {
+        Ok(outcome) => outcome.exitCode,
+        Err(err) => {
+            throwIllegalArgument(&mut env, err.to_string());
+            invocation::STATUS_FAILED
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn Java_dev_elide_cli_bridge_CliNativeBridge_runToolOnFileWithOutput<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass,
+    tool: JString<'local>,
+    file: JString<'local>,
+    listener: JObject<'local>,
+) -> jint {
+    let tool = match toolNamed(&mut env, &tool) {
         Some(tool) => tool,
-        None => panic!("Tool not found"),
+        None => return invocation::STATUS_FAILED,
     };
+    let file: String = env
+        .get_string(&file)
+        .expect("Couldn't get file string")
+        .into();
 
-    // switch by tool name
-    match tool.name {
-        "uv" => runUvOnSingleFile(env, &file),
-        "oxy" => runOxyOnSingleFile(env, &file),
-        "ruff" => runRuffOnSingleFile(env, &file),
-        _ => 1,
+    if listener.is_null() {
+        let _ = env.throw_new("java/lang/NullPointerException", "listener is null");
+        return invocation::STATUS_FAILED;
+    }
+    // resolved against the interface, since lambda listeners are anonymous classes unknown to native-image
+    let onLine = match env.get_method_id(
+        "dev/elide/cli/bridge/ToolOutputListener",
+        "onLine",
+        "(ILjava/lang/String;)V",
+    ) {
+        Ok(onLine) => onLine,
+        Err(_) => return invocation::STATUS_FAILED,
+    };
+
+    let mut delivered = true;
+    let exit = captureOutput(
+        |out| runToolOnSingleFile(tool, &file, out).exitCode,
+        |line| {
+            delivered = deliverLine(&mut env, &listener, onLine, line);
+            delivered
+        },
+    );
+    match exit {
+        Ok(exit) if delivered => exit,
+        _ => invocation::STATUS_FAILED,
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn Java_dev_elide_cli_bridge_CliNativeBridge_cpuInfo(
+    env: JNIEnv,
+    _class: JClass,
+) -> jstring {
+    let info = serde_json::to_string(&system::cpuSummary()).unwrap();
+    env.new_string(info).unwrap().into_raw()
+}
+
+#[no_mangle]
+pub extern "C" fn Java_dev_elide_cli_bridge_CliNativeBridge_loadAverage(
+    env: JNIEnv,
+    _class: JClass,
+) -> jstring {
+    let info = serde_json::to_string(&system::loadAverage()).unwrap();
+    env.new_string(info).unwrap().into_raw()
+}
+
+#[no_mangle]
+pub extern "C" fn Java_dev_elide_cli_bridge_CliNativeBridge_memoryInfo(
+    env: JNIEnv,
+    _class: JClass,
+) -> jstring {
+    let info = serde_json::to_string(&system::memoryInfo()).unwrap();
+    env.new_string(info).unwrap().into_raw()
+}
+
+#[no_mangle]
+pub extern "C" fn Java_dev_elide_cli_bridge_CliNativeBridge_networkInterfaces(
+    env: JNIEnv,
+    _class: JClass,
+) -> jstring {
+    let info = serde_json::to_string(&system::networkInterfaces()).unwrap();
+    env.new_string(info).unwrap().into_raw()
+}
+
+#[no_mangle]
+pub extern "C" fn Java_dev_elide_cli_bridge_CliNativeBridge_runToolOnFileForResult<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass,
+    tool: JString<'local>,
+    file: JString<'local>,
+) -> jstring {
+    let tool = match toolNamed(&mut env, &tool) {
+        Some(tool) => tool,
+        None => return std::ptr::null_mut(),
+    };
+    let file: String = env
+        .get_string(&file)
+        .expect("Couldn't get file string")
+        .into();
+
+    let result = serde_json::to_string(&runToolForResult(tool, &file)).unwrap();
+    env.new_string(result).unwrap().into_raw()
+}
+
+#[no_mangle]
+pub extern "C" fn Java_dev_elide_cli_bridge_CliNativeBridge_startTool<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass,
+    tool: JString<'local>,
+    files: JObjectArray<'local>,
+) -> jlong {
+    let tool = match toolNamed(&mut env, &tool) {
+        Some(tool) => tool,
+        None => return 0,
+    };
+
+    let files = match stringArray(&mut env, &files) {
+        Some(files) => files,
+        None => return 0,
+    };
+    invocation::start(move |cancel, progress| runToolOnFiles(tool, &files, cancel, progress))
+}
+
+#[no_mangle]
+pub extern "C" fn Java_dev_elide_cli_bridge_CliNativeBridge_pollTool(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jint {
+    invocation::poll(handle)
+}
+
+#[no_mangle]
+pub extern "C" fn Java_dev_elide_cli_bridge_CliNativeBridge_toolProgress(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jstring {
+    let events = serde_json::to_string(&invocation::progress(handle)).unwrap();
+    env.new_string(events).unwrap().into_raw()
+}
+
+#[no_mangle]
+pub extern "C" fn Java_dev_elide_cli_bridge_CliNativeBridge_cancelTool(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jboolean {
+    if invocation::cancel(handle) {
+        JNI_TRUE
+    } else {
+        JNI_FALSE
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn Java_dev_elide_cli_bridge_CliNativeBridge_toolCapabilities<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass,
+    tool: JString<'local>,
+) -> jstring {
+    let tool = match toolNamed(&mut env, &tool) {
+        Some(tool) => tool,
+        None => return std::ptr::null_mut(),
+    };
+    let capabilities = serde_json::to_string(&tool.capabilities).unwrap();
+    env.new_string(capabilities).unwrap().into_raw()
+}
+
+#[no_mangle]
+pub extern "C" fn Java_dev_elide_cli_bridge_CliNativeBridge_toolsForFile<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass,
+    file: JString<'local>,
+) -> jobjectArray {
+    let input: String = env
+        .get_string(&file)
+        .expect("Couldn't get file string")
+        .into();
+    let ext = std::path::Path::new(&input)
+        .extension()
+        .and_then(|x| x.to_str())
+        .unwrap_or("");
+    let mut tools: Vec<&'static str> = TOOL_MAP
+        .values()
+        .filter(|tool| tool.handlesExtension(ext))
+        .map(|tool| tool.name)
+        .collect();
+    tools.sort();
+
+    let array = env
+        .new_object_array(
+            tools.len() as i32,
+            "java/lang/String",
+            env.new_string("").unwrap(),
+        )
+        .unwrap();
+    for (i, tool) in tools.iter().enumerate() {
+        let tool = env.new_string(*tool).unwrap();
+        env.set_object_array_element(&array, i as i32, tool)
+            .unwrap();
     }
+    array.into_raw()
 }
@@ -0,0 +1,78 @@
+/*
+ * Copyright (c) 2024 Elide Technologies, Inc.
+ *
+ * Licensed under the MIT license (the "License"); you may not use this file except in compliance
+ * with the License. You may obtain a copy of the License at
+ *
+ *   https://opensource.org/license/mit/
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+ * an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. See the
+ * License for the specific language governing permissions and limitations under the License.
+ */
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+// Maximum number of undrained events kept per invocation; the oldest are dropped first.
+const MAX_PENDING_EVENTS: usize = 256;
+
+#[typeshare::typeshare]
+#[derive(Clone, Hash, Eq, PartialEq, Debug, Serialize)]
+pub struct ProgressEvent {
+    pub percent: u8,
+    pub message: String,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ProgressReporter(Arc<Mutex<VecDeque<ProgressEvent>>>);
+
+impl ProgressReporter {
+    pub fn report(&self, percent: u8, message: impl Into<String>) {
+        let mut events = self.0.lock().unwrap();
+        if events.len() == MAX_PENDING_EVENTS {
+            events.pop_front();
+        }
+        events.push_back(ProgressEvent {
+            percent: percent.min(100),
+            message: message.into(),
+        });
+    }
+
+    pub fn isEmpty(&self) -> bool {
+        self.0.lock().unwrap().is_empty()
+    }
+
+    // Take all events reported since the last drain, oldest first.
+    pub fn drain(&self) -> Vec<ProgressEvent> {
+        self.0.lock().unwrap().drain(..).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clampsPercent() {
+        let progress = ProgressReporter::default();
+        progress.report(250, "overshoot");
+        assert_eq!(progress.drain()[0].percent, 100);
+    }
+
+    #[test]
+    fn dropsOldestEventsPastCap() {
+        let progress = ProgressReporter::default();
+        for i in 0..MAX_PENDING_EVENTS + 10 {
+            progress.report(0, i.to_string());
+        }
+        let events = progress.drain();
+        assert_eq!(events.len(), MAX_PENDING_EVENTS);
+        assert_eq!(events[0].message, "10");
+        assert_eq!(
+            events.last().unwrap().message,
+            (MAX_PENDING_EVENTS + 9).to_string()
+        );
+        assert!(progress.isEmpty());
+    }
+}
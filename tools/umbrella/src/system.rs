@@ -0,0 +1,150 @@
+/*
+ * Copyright (c) 2024 Elide Technologies, Inc.
+ *
+ * Licensed under the MIT license (the "License"); you may not use this file except in compliance
+ * with the License. You may obtain a copy of the License at
+ *
+ *   https://opensource.org/license/mit/
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+ * an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. See the
+ * License for the specific language governing permissions and limitations under the License.
+ */
+use serde::Serialize;
+use sysinfo::{Networks, System};
+
+// Types with 64-bit fields can't go through typeshare; their JVM models live in `system.kt`.
+
+#[derive(Clone, Hash, Eq, PartialEq, Debug, Serialize)]
+pub struct CpuInfo {
+    pub model: String,
+    pub vendor: String,
+    pub frequency: u64,
+}
+
+#[derive(Clone, Hash, Eq, PartialEq, Debug, Serialize)]
+pub struct CpuSummary {
+    pub logicalCores: u32,
+    pub physicalCores: Option<u32>,
+    pub cpus: Vec<CpuInfo>,
+}
+
+#[typeshare::typeshare]
+#[derive(Clone, PartialEq, Debug, Serialize)]
+pub struct LoadAverage {
+    pub one: f64,
+    pub five: f64,
+    pub fifteen: f64,
+}
+
+#[derive(Clone, Hash, Eq, PartialEq, Debug, Serialize)]
+pub struct MemoryInfo {
+    pub total: u64,
+    pub free: u64,
+    pub available: u64,
+    pub totalSwap: u64,
+    pub freeSwap: u64,
+}
+
+#[typeshare::typeshare]
+#[derive(Clone, Hash, Eq, PartialEq, Debug, Serialize)]
+pub struct InterfaceAddress {
+    pub address: String,
+    pub family: &'static str,
+    pub prefix: u8,
+}
+
+#[typeshare::typeshare]
+#[derive(Clone, Hash, Eq, PartialEq, Debug, Serialize)]
+pub struct NetworkInterface {
+    pub name: String,
+    pub mac: String,
+    pub addresses: Vec<InterfaceAddress>,
+}
+
+// Gather CPU model, core counts, and per-core frequency (in MHz).
+pub fn cpuSummary() -> CpuSummary {
+    let mut sys = System::new();
+    sys.refresh_cpu_all();
+    let cpus: Vec<CpuInfo> = sys
+        .cpus()
+        .iter()
+        .map(|cpu| CpuInfo {
+            model: cpu.brand().trim().to_string(),
+            vendor: cpu.vendor_id().to_string(),
+            frequency: cpu.frequency(),
+        })
+        .collect();
+
+    CpuSummary {
+        logicalCores: cpus.len() as u32,
+        physicalCores: sys.physical_core_count().map(|x| x as u32),
+        cpus,
+    }
+}
+
+// Load averages over 1, 5, and 15 minutes; always zero on Windows.
+pub fn loadAverage() -> LoadAverage {
+    let load = System::load_average();
+    LoadAverage {
+        one: load.one,
+        five: load.five,
+        fifteen: load.fifteen,
+    }
+}
+
+// Physical and swap memory, in bytes.
+pub fn memoryInfo() -> MemoryInfo {
+    let mut sys = System::new();
+    sys.refresh_memory();
+    MemoryInfo {
+        total: sys.total_memory(),
+        free: sys.free_memory(),
+        available: sys.available_memory(),
+        totalSwap: sys.total_swap(),
+        freeSwap: sys.free_swap(),
+    }
+}
+
+// Network interfaces with their hardware and IP addresses, sorted by name.
+pub fn networkInterfaces() -> Vec<NetworkInterface> {
+    let networks = Networks::new_with_refreshed_list();
+    let mut interfaces: Vec<NetworkInterface> = networks
+        .iter()
+        .map(|(name, data)| NetworkInterface {
+            name: name.clone(),
+            mac: data.mac_address().to_string(),
+            addresses: data
+                .ip_networks()
+                .iter()
+                .map(|net| InterfaceAddress {
+                    address: net.addr.to_string(),
+                    family: if net.addr.is_ipv4() { "IPv4" } else { "IPv6" },
+                    prefix: net.prefix,
+                })
+                .collect(),
+        })
+        .collect();
+    interfaces.sort_by(|a, b| a.name.cmp(&b.name));
+    interfaces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reportsMemory() {
+        let memory = memoryInfo();
+        assert!(memory.total > 0);
+        assert!(memory.free <= memory.total);
+    }
+
+    #[test]
+    fn sortsInterfacesByName() {
+        let names: Vec<String> = networkInterfaces().into_iter().map(|x| x.name).collect();
+        let mut sorted = names.clone();
+        sorted.sort();
+        assert_eq!(names, sorted);
+    }
+}
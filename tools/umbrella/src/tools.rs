@@ -19,14 +19,39 @@ pub enum ToolType {
     Compiler,
 }
 
+// What the native bridge can run for a tool, used by the JVM to route files; not everything the upstream tool supports.
 #[typeshare::typeshare]
-#[derive(Clone, Hash, Eq, PartialEq, Debug, Serialize, Deserialize)]
+#[derive(Clone, Hash, Eq, PartialEq, Debug, Serialize)]
+pub struct ToolCapabilities {
+    pub subcommands: &'static [&'static str],
+    pub extensions: &'static [&'static str],
+    pub configFiles: &'static [&'static str],
+    pub canFormat: bool,
+    pub canLint: bool,
+    pub canFix: bool,
+}
+
+// Serialize-only: tool info is defined statically here and never read back, and serde can't deserialize the borrowed
+// `&'static [&'static str]` lists in `ToolCapabilities`.
+#[typeshare::typeshare]
+#[derive(Clone, Hash, Eq, PartialEq, Debug, Serialize)]
 pub struct ToolInfo {
     pub name: &'static str,
     pub version: &'static str,
     pub language: &'static str,
     pub experimental: bool,
     pub kind: ToolType,
+    pub capabilities: ToolCapabilities,
+}
+
+impl ToolInfo {
+    // Whether this tool accepts files with the given extension (without the leading dot).
+    pub fn handlesExtension(&self, ext: &str) -> bool {
+        self.capabilities
+            .extensions
+            .iter()
+            .any(|x| x.eq_ignore_ascii_case(ext))
+    }
 }
 
 // Library version of the tooling layer.
@@ -41,6 +66,14 @@ pub static UV_INFO: ToolInfo = ToolInfo {
     language: "python",
     experimental: true,
     kind: ToolType::Linter,
+    capabilities: ToolCapabilities {
+        subcommands: &[],
+        extensions: &[],
+        configFiles: &["pyproject.toml", "uv.toml"],
+        canFormat: false,
+        canLint: false,
+        canFix: false,
+    },
 };
 
 pub static RUFF_INFO: ToolInfo = ToolInfo {
@@ -49,6 +82,14 @@ pub static RUFF_INFO: ToolInfo = ToolInfo {
     language: "python",
     experimental: true,
     kind: ToolType::Linter,
+    capabilities: ToolCapabilities {
+        subcommands: &["check"],
+        extensions: &["py", "pyi"],
+        configFiles: &["pyproject.toml", "ruff.toml", ".ruff.toml"],
+        canFormat: false,
+        canLint: true,
+        canFix: false,
+    },
 };
 
 pub static OXY_INFO: ToolInfo = ToolInfo {
@@ -57,4 +98,12 @@ pub static OXY_INFO: ToolInfo = ToolInfo {
     language: "js",
     experimental: false,
     kind: ToolType::Compiler,
+    capabilities: ToolCapabilities {
+        subcommands: &["lint"],
+        extensions: &["js", "mjs", "cjs", "jsx", "ts", "mts", "cts", "tsx"],
+        configFiles: &[".oxlintrc.json"],
+        canFormat: false,
+        canLint: true,
+        canFix: false,
+    },
 };